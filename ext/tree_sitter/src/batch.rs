@@ -0,0 +1,94 @@
+use crate::language::get_language_internal;
+use crate::tree::Tree;
+use magnus::{Error, RArray, RHash, Ruby, TryConvert, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// `TreeSitter.parse_all(language:, sources:)` — parses many sources across
+/// a worker pool so a Ruby program can use more than one core to parse,
+/// which a single `Parser` (bound to the GVL-serialized main thread) cannot.
+pub fn parse_all(args: &[Value]) -> Result<RArray, Error> {
+    let ruby = Ruby::get().unwrap();
+
+    let kwargs = args
+        .first()
+        .copied()
+        .and_then(RHash::from_value)
+        .ok_or_else(|| {
+            Error::new(
+                ruby.exception_arg_error(),
+                "expected keyword arguments: language:, sources:",
+            )
+        })?;
+
+    let language_value = kwargs.get("language").ok_or_else(|| {
+        Error::new(ruby.exception_arg_error(), "missing keyword: language")
+    })?;
+    let language_name: String = TryConvert::try_convert(language_value)?;
+
+    let sources_value = kwargs.get("sources").ok_or_else(|| {
+        Error::new(ruby.exception_arg_error(), "missing keyword: sources")
+    })?;
+    let sources_array: RArray = TryConvert::try_convert(sources_value)?;
+    let sources: Vec<String> = sources_array
+        .each()
+        .map(|v| TryConvert::try_convert(v?))
+        .collect::<Result<_, Error>>()?;
+
+    let ts_language = get_language_internal(&language_name)?;
+
+    let worker_count = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(sources.len().max(1));
+
+    let trees: Mutex<Vec<Option<tree_sitter::Tree>>> =
+        Mutex::new((0..sources.len()).map(|_| None).collect());
+    let next_index = AtomicUsize::new(0);
+
+    ruby.without_gvl(
+        || {
+            thread::scope(|scope| {
+                for _ in 0..worker_count {
+                    let ts_language = ts_language.clone();
+                    let sources = &sources;
+                    let trees = &trees;
+                    let next_index = &next_index;
+
+                    scope.spawn(move || {
+                        let mut parser = tree_sitter::Parser::new();
+                        if parser.set_language(&ts_language).is_err() {
+                            return;
+                        }
+
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::SeqCst);
+                            if index >= sources.len() {
+                                break;
+                            }
+                            let tree = parser.parse(&sources[index], None);
+                            trees.lock().unwrap()[index] = tree;
+                        }
+                    });
+                }
+            });
+        },
+        None::<fn()>,
+    );
+
+    let trees = trees.into_inner().unwrap();
+    let array = ruby.ary_new();
+    for (tree, source) in trees.into_iter().zip(sources.into_iter()) {
+        match tree {
+            Some(tree) => {
+                let _ = array.push(Tree::new(tree, source, language_name.clone()));
+            }
+            None => {
+                let _ = array.push(ruby.qnil());
+            }
+        }
+    }
+
+    Ok(array)
+}
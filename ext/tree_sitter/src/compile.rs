@@ -0,0 +1,144 @@
+use crate::language::register_language;
+use magnus::{Error, RHash, Ruby, TryConvert, Value};
+use std::path::PathBuf;
+
+/// `cc::Build` normally learns the target/host triple from the `TARGET`/
+/// `HOST` env vars Cargo sets for `build.rs`, which aren't present here since
+/// we're compiling at Ruby runtime, not at `cargo build` time. Reconstruct a
+/// triple good enough for `cc` to pick a compiler from the running process's
+/// own arch/OS — this extension only ever runs on the host it compiles for.
+fn host_triple() -> String {
+    let arch = std::env::consts::ARCH;
+    if cfg!(target_os = "macos") {
+        format!("{}-apple-darwin", arch)
+    } else if cfg!(target_os = "windows") {
+        let env = if cfg!(target_env = "gnu") { "gnu" } else { "msvc" };
+        format!("{}-pc-windows-{}", arch, env)
+    } else {
+        let env = if cfg!(target_env = "musl") { "musl" } else { "gnu" };
+        format!("{}-unknown-linux-{}", arch, env)
+    }
+}
+
+/// `TreeSitter.compile_language(name:, src_dir:)` — builds a grammar's
+/// generated parser (and optional external scanner) straight from a cloned
+/// `tree-sitter-<lang>` checkout's `src/` directory into a temporary shared
+/// library, then registers it the same way `register_language` does.
+pub fn compile_language(args: &[Value]) -> Result<(), Error> {
+    let ruby = Ruby::get().unwrap();
+
+    let kwargs = args
+        .first()
+        .copied()
+        .and_then(RHash::from_value)
+        .ok_or_else(|| {
+            Error::new(
+                ruby.exception_arg_error(),
+                "expected keyword arguments: name:, src_dir:",
+            )
+        })?;
+
+    let name_value = kwargs
+        .get("name")
+        .ok_or_else(|| Error::new(ruby.exception_arg_error(), "missing keyword: name"))?;
+    let name: String = TryConvert::try_convert(name_value)?;
+
+    let src_dir_value = kwargs
+        .get("src_dir")
+        .ok_or_else(|| Error::new(ruby.exception_arg_error(), "missing keyword: src_dir"))?;
+    let src_dir: String = TryConvert::try_convert(src_dir_value)?;
+
+    let src_path = PathBuf::from(&src_dir);
+    let parser_c = src_path.join("parser.c");
+    if !parser_c.is_file() {
+        return Err(Error::new(
+            ruby.exception_runtime_error(),
+            format!("No parser.c found in '{}'", src_dir),
+        ));
+    }
+
+    let mut sources = vec![parser_c];
+    let scanner_c = src_path.join("scanner.c");
+    let scanner_cc = src_path.join("scanner.cc");
+    let is_cpp = scanner_cc.is_file();
+    if scanner_c.is_file() {
+        sources.push(scanner_c);
+    } else if is_cpp {
+        sources.push(scanner_cc);
+    }
+
+    let out_dir = std::env::temp_dir().join(format!(
+        "tree_sitter-{}-{}-{}",
+        name,
+        std::process::id(),
+        sources.len()
+    ));
+    std::fs::create_dir_all(&out_dir).map_err(|e| {
+        Error::new(
+            ruby.exception_runtime_error(),
+            format!("Failed to create build directory: {}", e),
+        )
+    })?;
+
+    let lib_extension = if cfg!(target_os = "macos") {
+        "dylib"
+    } else if cfg!(target_os = "windows") {
+        "dll"
+    } else {
+        "so"
+    };
+    let lib_path = out_dir.join(format!("lib{}.{}", name, lib_extension));
+
+    let triple = host_triple();
+    let mut build = cc::Build::new();
+    build
+        .include(&src_path)
+        .cpp(is_cpp)
+        .opt_level(2)
+        .target(&triple)
+        .host(&triple);
+    let compiler = build.try_get_compiler().map_err(|e| {
+        Error::new(
+            ruby.exception_runtime_error(),
+            format!("Failed to detect a C/C++ compiler: {}", e),
+        )
+    })?;
+
+    let mut command = compiler.to_command();
+    if compiler.is_like_msvc() {
+        // MSVC's cl.exe doesn't understand -shared/-fPIC: `/LD` builds a DLL
+        // directly, and position-independent code isn't a separate concept
+        // on Windows.
+        command.arg("/LD");
+        for source in &sources {
+            command.arg(source);
+        }
+        command.arg(format!("/Fe:{}", lib_path.display()));
+    } else {
+        command.arg("-shared").arg("-fPIC");
+        for source in &sources {
+            command.arg(source);
+        }
+        command.arg("-o").arg(&lib_path);
+    }
+
+    let output = command.output().map_err(|e| {
+        Error::new(
+            ruby.exception_runtime_error(),
+            format!("Failed to invoke compiler: {}", e),
+        )
+    })?;
+
+    if !output.status.success() {
+        return Err(Error::new(
+            ruby.exception_runtime_error(),
+            format!(
+                "Failed to compile grammar '{}':\n{}",
+                name,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        ));
+    }
+
+    register_language(name, lib_path.to_string_lossy().into_owned())
+}
@@ -46,6 +46,22 @@ pub fn register_language(name: String, library_path: String) -> Result<(), Error
 
     let language: tree_sitter::Language = (*language_fn).into();
 
+    let abi_version = language.abi_version();
+    if !(tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION)
+        .contains(&abi_version)
+    {
+        return Err(Error::new(
+            ruby.exception_runtime_error(),
+            format!(
+                "Incompatible language ABI version {} in '{}' (expected {}..={})",
+                abi_version,
+                library_path,
+                tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION,
+                tree_sitter::LANGUAGE_VERSION
+            ),
+        ));
+    }
+
     // Store language function in registry
     let mut registry = LANGUAGES.write().map_err(|_| {
         Error::new(
@@ -149,4 +165,14 @@ impl Language {
     pub fn node_kind_count(&self) -> usize {
         self.inner.node_kind_count()
     }
+
+    /// Returns an iterator over the symbols valid at `state_id`, or `nil` if
+    /// the state is invalid. Pair this with `Node#parse_state` on an
+    /// `error?` node to list the tokens the parser expected there.
+    pub fn lookahead_iterator(
+        &self,
+        state_id: u16,
+    ) -> Option<crate::lookahead_iterator::LookaheadIterator> {
+        crate::lookahead_iterator::LookaheadIterator::new(self.inner.clone(), state_id)
+    }
 }
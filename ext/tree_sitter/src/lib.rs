@@ -1,10 +1,14 @@
+mod batch;
+mod compile;
 mod language;
+mod lookahead_iterator;
 mod node;
 mod parser;
 mod point;
 mod query;
 mod range;
 mod tree;
+mod tree_cursor;
 
 use magnus::{function, method, prelude::*, Error, Ruby};
 
@@ -18,6 +22,8 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     )?;
     module.define_singleton_method("language", function!(language::get_language, 1))?;
     module.define_singleton_method("languages", function!(language::list_languages, 0))?;
+    module.define_singleton_method("parse_all", function!(batch::parse_all, -1))?;
+    module.define_singleton_method("compile_language", function!(compile::compile_language, -1))?;
 
     let language_class = module.define_class("Language", ruby.class_object())?;
     language_class.define_method("name", method!(language::Language::name, 0))?;
@@ -26,6 +32,40 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
         "node_kind_count",
         method!(language::Language::node_kind_count, 0),
     )?;
+    language_class.define_method(
+        "lookahead_iterator",
+        method!(language::Language::lookahead_iterator, 1),
+    )?;
+
+    let lookahead_iterator_class =
+        module.define_class("LookaheadIterator", ruby.class_object())?;
+    lookahead_iterator_class.define_method(
+        "current_symbol",
+        method!(lookahead_iterator::LookaheadIterator::current_symbol, 0),
+    )?;
+    lookahead_iterator_class.define_method(
+        "current_symbol_name",
+        method!(
+            lookahead_iterator::LookaheadIterator::current_symbol_name,
+            0
+        ),
+    )?;
+    lookahead_iterator_class.define_method(
+        "reset_state",
+        method!(lookahead_iterator::LookaheadIterator::reset_state, 1),
+    )?;
+    lookahead_iterator_class.define_method(
+        "reset",
+        method!(lookahead_iterator::LookaheadIterator::reset, 1),
+    )?;
+    lookahead_iterator_class.define_method(
+        "next_symbol",
+        method!(lookahead_iterator::LookaheadIterator::next_symbol, 0),
+    )?;
+    lookahead_iterator_class.define_method(
+        "each",
+        method!(lookahead_iterator::LookaheadIterator::each, 0),
+    )?;
 
     let parser_class = module.define_class("Parser", ruby.class_object())?;
     parser_class.define_singleton_method("new", function!(parser::Parser::new, 0))?;
@@ -38,11 +78,26 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
         method!(parser::Parser::set_timeout_micros, 1),
     )?;
     parser_class.define_method("reset", method!(parser::Parser::reset, 0))?;
+    parser_class.define_method("cancel", method!(parser::Parser::cancel, 0))?;
+    parser_class.define_method(
+        "included_ranges=",
+        method!(parser::Parser::set_included_ranges, 1),
+    )?;
+    parser_class.define_method(
+        "included_ranges",
+        method!(parser::Parser::included_ranges, 0),
+    )?;
 
     let tree_class = module.define_class("Tree", ruby.class_object())?;
     tree_class.define_method("root_node", method!(tree::Tree::root_node, 0))?;
     tree_class.define_method("source", method!(tree::Tree::source, 0))?;
     tree_class.define_method("language", method!(tree::Tree::language, 0))?;
+    tree_class.define_method("edit", method!(tree::Tree::edit, -1))?;
+    tree_class.define_method("walk", method!(tree::Tree::walk, 0))?;
+    tree_class.define_method(
+        "print_dot_graph",
+        method!(tree::Tree::print_dot_graph, 1),
+    )?;
 
     let node_class = module.define_class("Node", ruby.class_object())?;
 
@@ -82,6 +137,13 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     node_class.define_method("error?", method!(node::Node::is_error, 0))?;
     node_class.define_method("has_error?", method!(node::Node::has_error, 0))?;
     node_class.define_method("has_changes?", method!(node::Node::has_changes, 0))?;
+    node_class.define_method("parse_state", method!(node::Node::parse_state, 0))?;
+    node_class.define_method(
+        "next_parse_state",
+        method!(node::Node::next_parse_state, 0),
+    )?;
+    node_class.define_method("grammar_name", method!(node::Node::grammar_name, 0))?;
+    node_class.define_method("grammar_id", method!(node::Node::grammar_id, 0))?;
 
     // Position
     node_class.define_method("start_byte", method!(node::Node::start_byte, 0))?;
@@ -97,6 +159,8 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     node_class.define_method("inspect", method!(node::Node::inspect, 0))?;
     node_class.define_method("==", method!(node::Node::eq, 1))?;
     node_class.define_method("eql?", method!(node::Node::eq, 1))?;
+    node_class.define_method("walk", method!(node::Node::walk, 0))?;
+    node_class.define_method("to_dot", method!(node::Node::to_dot, 0))?;
 
     let point_class = module.define_class("Point", ruby.class_object())?;
     point_class.define_singleton_method("new", function!(point::Point::new, 2))?;
@@ -108,6 +172,7 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
 
     // Range class
     let range_class = module.define_class("Range", ruby.class_object())?;
+    range_class.define_singleton_method("new", function!(range::Range::new, 4))?;
     range_class.define_method("start_byte", method!(range::Range::start_byte, 0))?;
     range_class.define_method("end_byte", method!(range::Range::end_byte, 0))?;
     range_class.define_method("start_point", method!(range::Range::start_point, 0))?;
@@ -115,10 +180,49 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     range_class.define_method("size", method!(range::Range::size, 0))?;
     range_class.define_method("inspect", method!(range::Range::inspect, 0))?;
 
+    let tree_cursor_class = module.define_class("TreeCursor", ruby.class_object())?;
+    tree_cursor_class.define_method(
+        "goto_first_child",
+        method!(tree_cursor::TreeCursor::goto_first_child, 0),
+    )?;
+    tree_cursor_class.define_method(
+        "goto_next_sibling",
+        method!(tree_cursor::TreeCursor::goto_next_sibling, 0),
+    )?;
+    tree_cursor_class.define_method(
+        "goto_parent",
+        method!(tree_cursor::TreeCursor::goto_parent, 0),
+    )?;
+    tree_cursor_class.define_method(
+        "goto_first_child_for_byte",
+        method!(tree_cursor::TreeCursor::goto_first_child_for_byte, 1),
+    )?;
+    tree_cursor_class.define_method(
+        "current_node",
+        method!(tree_cursor::TreeCursor::current_node, 0),
+    )?;
+    tree_cursor_class.define_method(
+        "current_field_name",
+        method!(tree_cursor::TreeCursor::current_field_name, 0),
+    )?;
+    tree_cursor_class.define_method("reset", method!(tree_cursor::TreeCursor::reset, 1))?;
+
     let query_class = module.define_class("Query", ruby.class_object())?;
     query_class.define_singleton_method("new", function!(query::Query::new, 2))?;
     query_class.define_method("capture_names", method!(query::Query::capture_names, 0))?;
     query_class.define_method("pattern_count", method!(query::Query::pattern_count, 0))?;
+    query_class.define_method(
+        "general_predicates",
+        method!(query::Query::general_predicates, 1),
+    )?;
+    query_class.define_method(
+        "property_predicates",
+        method!(query::Query::property_predicates, 1),
+    )?;
+    query_class.define_method(
+        "property_settings",
+        method!(query::Query::property_settings, 1),
+    )?;
 
     let cursor_class = module.define_class("QueryCursor", ruby.class_object())?;
     cursor_class.define_singleton_method("new", function!(query::QueryCursor::new, 0))?;
@@ -136,5 +240,9 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     capture_class.define_method("name", method!(query::QueryCapture::name, 0))?;
     capture_class.define_method("node", method!(query::QueryCapture::node, 0))?;
 
+    let predicate_class = module.define_class("QueryPredicate", ruby.class_object())?;
+    predicate_class.define_method("operator", method!(query::RawPredicate::operator, 0))?;
+    predicate_class.define_method("args", method!(query::RawPredicate::args, 0))?;
+
     Ok(())
 }
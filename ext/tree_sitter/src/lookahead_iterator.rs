@@ -0,0 +1,60 @@
+use magnus::block::{block_given, Yield};
+use magnus::prelude::*;
+use magnus::Obj;
+use std::cell::RefCell;
+
+/// Wraps `tree_sitter::LookaheadIterator`, letting callers ask "what symbols
+/// are valid in this parse state?" — useful for autocomplete and for
+/// building better error messages around an `error?` node.
+#[magnus::wrap(class = "TreeSitter::LookaheadIterator")]
+pub struct LookaheadIterator {
+    // Kept around so `reset` can hand it back to `tree_sitter::LookaheadIterator::reset`.
+    // Cheap to clone: `tree_sitter::Language` is reference-counted internally.
+    language: tree_sitter::Language,
+    inner: RefCell<tree_sitter::LookaheadIterator>,
+}
+
+impl LookaheadIterator {
+    pub fn new(language: tree_sitter::Language, state_id: u16) -> Option<Self> {
+        let iter = language.lookahead_iterator(state_id)?;
+        Some(Self {
+            language,
+            inner: RefCell::new(iter),
+        })
+    }
+
+    pub fn current_symbol(&self) -> u16 {
+        self.inner.borrow().current_symbol()
+    }
+
+    pub fn current_symbol_name(&self) -> String {
+        self.inner.borrow().current_symbol_name().to_string()
+    }
+
+    pub fn reset_state(&self, state_id: u16) -> bool {
+        self.inner.borrow_mut().reset_state(state_id)
+    }
+
+    pub fn reset(&self, state_id: u16) -> bool {
+        self.inner.borrow_mut().reset(&self.language, state_id)
+    }
+
+    pub fn next_symbol(&self) -> Option<u16> {
+        self.inner.borrow_mut().next()
+    }
+
+    pub fn each(rb_self: Obj<Self>) -> Yield<std::vec::IntoIter<u16>> {
+        if !block_given() {
+            return Yield::Enumerator(rb_self.enumeratorize("each", ()));
+        }
+
+        let mut symbols = Vec::new();
+        {
+            let mut inner = rb_self.inner.borrow_mut();
+            while let Some(symbol) = inner.next() {
+                symbols.push(symbol);
+            }
+        }
+        Yield::Iter(symbols.into_iter())
+    }
+}
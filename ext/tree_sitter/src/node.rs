@@ -32,6 +32,10 @@ pub struct Node {
     child_count: usize,
     named_child_count: usize,
     sexp: String,
+    parse_state: u16,
+    next_parse_state: u16,
+    grammar_name: String,
+    grammar_id: u16,
 }
 
 impl Node {
@@ -54,6 +58,10 @@ impl Node {
             child_count: ts_node.child_count(),
             named_child_count: ts_node.named_child_count(),
             sexp: ts_node.to_sexp(),
+            parse_state: ts_node.parse_state(),
+            next_parse_state: ts_node.next_parse_state(),
+            grammar_name: ts_node.grammar_name().to_string(),
+            grammar_id: ts_node.grammar_id(),
         }
     }
 
@@ -69,6 +77,15 @@ impl Node {
         root.descendant_for_byte_range(self.start_byte, self.end_byte)
     }
 
+    pub fn walk(&self) -> Option<crate::tree_cursor::TreeCursor> {
+        let ts_node = self.get_ts_node()?;
+        Some(crate::tree_cursor::TreeCursor::new(
+            self.tree.clone(),
+            self.source.clone(),
+            ts_node,
+        ))
+    }
+
     // Navigation methods
 
     pub fn parent(&self) -> Option<Node> {
@@ -194,6 +211,22 @@ impl Node {
         self.has_changes
     }
 
+    pub fn parse_state(&self) -> u16 {
+        self.parse_state
+    }
+
+    pub fn next_parse_state(&self) -> u16 {
+        self.next_parse_state
+    }
+
+    pub fn grammar_name(&self) -> &str {
+        &self.grammar_name
+    }
+
+    pub fn grammar_id(&self) -> u16 {
+        self.grammar_id
+    }
+
     // Position
     pub fn start_byte(&self) -> usize {
         self.start_byte
@@ -245,4 +278,75 @@ impl Node {
             && self.end_byte == other.end_byte
             && self.kind == other.kind
     }
+
+    /// Renders the subtree rooted at this node as a GraphViz DOT document,
+    /// for teaching/debugging without shelling out to the `tree-sitter` CLI.
+    pub fn to_dot(&self) -> String {
+        let Some(ts_node) = self.get_ts_node() else {
+            return String::new();
+        };
+        let mut out = String::from("digraph tree {\n");
+        let mut next_id = 0usize;
+        write_dot_node(&mut out, ts_node, None, &mut next_id);
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Escapes `"`, `\`, and newlines so a string is safe to embed inside a
+/// DOT quoted label. Needed because `node.kind()` is the literal token text
+/// for anonymous nodes — e.g. a `"` token in any grammar with quoted
+/// strings — which would otherwise close the label early.
+fn escape_dot_label(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn write_dot_node(
+    out: &mut String,
+    node: tree_sitter::Node,
+    parent: Option<(usize, Option<&str>)>,
+    next_id: &mut usize,
+) {
+    let this_id = *next_id;
+    *next_id += 1;
+
+    out.push_str(&format!(
+        "  node{} [label=\"{} [{}, {})\"];\n",
+        this_id,
+        escape_dot_label(node.kind()),
+        node.start_byte(),
+        node.end_byte()
+    ));
+
+    if let Some((parent_id, field_name)) = parent {
+        match field_name {
+            Some(name) => out.push_str(&format!(
+                "  node{} -> node{} [label=\"{}\"];\n",
+                parent_id,
+                this_id,
+                escape_dot_label(name)
+            )),
+            None => out.push_str(&format!("  node{} -> node{};\n", parent_id, this_id)),
+        }
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let field_name = cursor.field_name();
+            write_dot_node(out, cursor.node(), Some((this_id, field_name)), next_id);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use crate::language::{get_language_internal, Language};
+use crate::point::Point;
+use crate::range::Range;
 use crate::tree::Tree;
-use magnus::{prelude::*, Error, RString, Ruby, TryConvert, Value};
+use magnus::{prelude::*, Error, RArray, RHash, RString, Ruby, TryConvert, Value};
 use std::cell::RefCell;
 use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
 #[magnus::wrap(class = "TreeSitter::Parser")]
@@ -10,6 +13,7 @@ pub struct Parser {
     inner: RefCell<tree_sitter::Parser>,
     language_name: RefCell<Option<String>>,
     timeout_micros: RefCell<u64>,
+    cancelled: AtomicBool,
 }
 
 impl Parser {
@@ -19,6 +23,7 @@ impl Parser {
             inner: RefCell::new(parser),
             language_name: RefCell::new(None),
             timeout_micros: RefCell::new(0),
+            cancelled: AtomicBool::new(false),
         })
     }
 
@@ -63,19 +68,16 @@ impl Parser {
     pub fn parse(&self, args: &[Value]) -> Result<Option<Tree>, Error> {
         let ruby = Ruby::get().unwrap();
 
-        if args.is_empty() {
-            return Err(Error::new(
-                ruby.exception_arg_error(),
-                "wrong number of arguments",
-            ));
-        }
-
-        let source: String = <String as TryConvert>::try_convert(args[0])?;
-        let old_tree: Option<&Tree> = if args.len() > 1 && !args[1].is_nil() {
-            Some(<&Tree as TryConvert>::try_convert(args[1])?)
-        } else {
-            None
-        };
+        // The kwargs hash isn't always at a fixed position: it's `args[1]`
+        // when a source string is given, but `args[0]` in the block-based
+        // read-callback form, which has no source argument at all.
+        let old_tree: Option<&Tree> = args
+            .iter()
+            .find_map(|v| RHash::from_value(*v))
+            .and_then(|kwargs| kwargs.get("old_tree"))
+            .filter(|v| !v.is_nil())
+            .map(|v| <&Tree as TryConvert>::try_convert(v))
+            .transpose()?;
 
         let language_name = self.language_name.borrow().clone().ok_or_else(|| {
             Error::new(
@@ -85,21 +87,107 @@ impl Parser {
         })?;
 
         let mut parser = self.inner.borrow_mut();
-        let old_ts_tree = old_tree.map(|t| (*t.inner).clone());
-
+        let old_ts_tree = old_tree.map(|t| t.ts_tree());
         let timeout = *self.timeout_micros.borrow();
-        let result = if timeout > 0 {
+
+        // `args[0]` is the kwargs Hash, not a source string, when there's no
+        // source positional at all (the block-based read-callback form,
+        // possibly combined with `old_tree:`).
+        let source_arg: Option<String> = args
+            .first()
+            .copied()
+            .filter(|v| !v.is_nil() && RHash::from_value(*v).is_none())
+            .map(<String as TryConvert>::try_convert)
+            .transpose()?;
+        self.cancelled.store(false, Ordering::SeqCst);
+
+        // With a block and no source argument, read source chunks on demand
+        // instead of requiring the caller to materialize the whole document
+        // as one Ruby String up front (useful for rope/gap-buffer-backed
+        // editor buffers).
+        if source_arg.is_none() {
+            let block = ruby.block_proc()?;
+            let mut buffer = String::new();
+            let mut exhausted = false;
+            let error: RefCell<Option<Error>> = RefCell::new(None);
+
+            let mut source_callback = |offset: usize, point: tree_sitter::Point| -> &[u8] {
+                while !exhausted && offset >= buffer.len() {
+                    let chunk_result = block
+                        .call::<(usize, Point), Value>((offset, Point::from_ts(point)))
+                        .and_then(|v| {
+                            if v.is_nil() {
+                                Ok(None)
+                            } else {
+                                <String as TryConvert>::try_convert(v).map(Some)
+                            }
+                        });
+                    match chunk_result {
+                        Ok(Some(chunk)) if !chunk.is_empty() => buffer.push_str(&chunk),
+                        Ok(_) => exhausted = true, // nil or "" signals EOF
+                        Err(e) => {
+                            *error.borrow_mut() = Some(e);
+                            exhausted = true;
+                        }
+                    }
+                }
+
+                if offset < buffer.len() {
+                    &buffer.as_bytes()[offset..]
+                } else {
+                    &[]
+                }
+            };
+
             let start = Instant::now();
-            let source_bytes = source.as_bytes();
+            let cancelled = &self.cancelled;
             let mut progress_callback = |_: &tree_sitter::ParseState| {
-                if start.elapsed().as_micros() < timeout as u128 {
-                    ControlFlow::Continue(())
-                } else {
+                if cancelled.load(Ordering::SeqCst) {
+                    ControlFlow::Break(())
+                } else if timeout > 0 && start.elapsed().as_micros() >= timeout as u128 {
                     ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
                 }
             };
             let options =
                 tree_sitter::ParseOptions::new().progress_callback(&mut progress_callback);
+            let result =
+                parser.parse_with_options(&mut source_callback, old_ts_tree.as_deref(), Some(options));
+
+            if let Some(e) = error.into_inner() {
+                return Err(e);
+            }
+
+            return match result {
+                Some(tree) => Ok(Some(Tree::new(tree, buffer, language_name))),
+                None => Ok(None),
+            };
+        }
+
+        let source = source_arg.unwrap();
+
+        // A progress block is called back into Ruby on every tick, so this
+        // path can't release the GVL the way the plain-source path below
+        // does; calling into Ruby still gives MRI's own thread scheduler a
+        // chance to run `cancel` on another thread between ticks.
+        if let Ok(block) = ruby.block_proc() {
+            let start = Instant::now();
+            let cancelled = &self.cancelled;
+            let mut progress_callback = |state: &tree_sitter::ParseState| -> ControlFlow<()> {
+                if cancelled.load(Ordering::SeqCst) {
+                    return ControlFlow::Break(());
+                }
+                if timeout > 0 && start.elapsed().as_micros() >= timeout as u128 {
+                    return ControlFlow::Break(());
+                }
+                match block.call::<(usize,), Value>((state.current_byte_offset(),)) {
+                    Ok(v) if v.to_bool() => ControlFlow::Continue(()),
+                    _ => ControlFlow::Break(()),
+                }
+            };
+            let options = tree_sitter::ParseOptions::new().progress_callback(&mut progress_callback);
+            let source_bytes = source.as_bytes();
             let mut source_callback = |offset: usize, _: tree_sitter::Point| {
                 if offset < source_bytes.len() {
                     &source_bytes[offset..]
@@ -107,10 +195,47 @@ impl Parser {
                     &[]
                 }
             };
-            parser.parse_with_options(&mut source_callback, old_ts_tree.as_ref(), Some(options))
-        } else {
-            parser.parse(&source, old_ts_tree.as_ref())
-        };
+            let result =
+                parser.parse_with_options(&mut source_callback, old_ts_tree.as_deref(), Some(options));
+
+            return match result {
+                Some(tree) => Ok(Some(Tree::new(tree, source, language_name))),
+                None => Ok(None),
+            };
+        }
+
+        // No block, so nothing here calls back into Ruby: it's safe to
+        // release the GVL for the duration of the parse and let other Ruby
+        // threads run. The progress callback still checks `cancelled` on
+        // every tick so `Parser#cancel` can interrupt this from another
+        // thread even without a timeout.
+        let cancelled = &self.cancelled;
+        let result = ruby.without_gvl(
+            || {
+                let start = Instant::now();
+                let source_bytes = source.as_bytes();
+                let mut progress_callback = |_: &tree_sitter::ParseState| {
+                    if cancelled.load(Ordering::SeqCst) {
+                        ControlFlow::Break(())
+                    } else if timeout > 0 && start.elapsed().as_micros() >= timeout as u128 {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                };
+                let options =
+                    tree_sitter::ParseOptions::new().progress_callback(&mut progress_callback);
+                let mut source_callback = |offset: usize, _: tree_sitter::Point| {
+                    if offset < source_bytes.len() {
+                        &source_bytes[offset..]
+                    } else {
+                        &[]
+                    }
+                };
+                parser.parse_with_options(&mut source_callback, old_ts_tree.as_deref(), Some(options))
+            },
+            None::<fn()>,
+        );
 
         match result {
             Some(tree) => Ok(Some(Tree::new(tree, source, language_name))),
@@ -118,6 +243,13 @@ impl Parser {
         }
     }
 
+    /// Requests that an in-progress `parse` abort as soon as its progress
+    /// callback next runs, returning `nil`. Safe to call from another Ruby
+    /// thread while a parse is running in the background.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
     pub fn timeout_micros(&self) -> u64 {
         *self.timeout_micros.borrow()
     }
@@ -129,4 +261,48 @@ impl Parser {
     pub fn reset(&self) {
         self.inner.borrow_mut().reset();
     }
+
+    /// Restricts parsing to the given ranges, so only e.g. the JavaScript
+    /// spans inside an HTML document (or SQL inside a Ruby heredoc) get
+    /// parsed by a second, differently-languaged `Parser`.
+    pub fn set_included_ranges(&self, ranges: RArray) -> Result<(), Error> {
+        let ruby = Ruby::get().unwrap();
+
+        let mut ts_ranges = Vec::with_capacity(ranges.len());
+        for value in ranges.each() {
+            let range: &Range = TryConvert::try_convert(value?)?;
+            ts_ranges.push(tree_sitter::Range {
+                start_byte: range.start_byte(),
+                end_byte: range.end_byte(),
+                start_point: range.start_point().to_ts(),
+                end_point: range.end_point().to_ts(),
+            });
+        }
+
+        self.inner
+            .borrow_mut()
+            .set_included_ranges(&ts_ranges)
+            .map_err(|e| {
+                Error::new(
+                    ruby.exception_runtime_error(),
+                    format!("Failed to set included ranges: {:?}", e),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    pub fn included_ranges(&self) -> RArray {
+        let ruby = Ruby::get().unwrap();
+        let array = ruby.ary_new();
+        for range in self.inner.borrow().included_ranges() {
+            let _ = array.push(Range::new(
+                range.start_byte,
+                range.end_byte,
+                Point::from_ts(range.start_point),
+                Point::from_ts(range.end_point),
+            ));
+        }
+        array
+    }
 }
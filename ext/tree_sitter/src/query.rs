@@ -3,11 +3,64 @@ use crate::node::Node;
 use magnus::{Error, RArray, Ruby};
 use std::cell::RefCell;
 use streaming_iterator::StreamingIterator;
+use tree_sitter::QueryPredicateArg;
+
+/// A predicate attached to a pattern that this gem doesn't evaluate itself:
+/// either a `#set!`/`#is?`/`#is-not?` property predicate (the crate parses
+/// these but leaves applying them to the caller) or a genuinely custom
+/// directive. The crate does fully evaluate the standard text predicates
+/// (`#eq?`, `#match?`, `#any-of?`, and their `not-` negations) before a match
+/// is ever yielded from `QueryCursor`, so those never show up here.
+/// Preserved verbatim so callers can implement their own handling.
+#[magnus::wrap(class = "TreeSitter::QueryPredicate")]
+#[derive(Clone)]
+pub struct RawPredicate {
+    operator: String,
+    args: Vec<String>,
+}
+
+impl RawPredicate {
+    pub fn operator(&self) -> String {
+        self.operator.clone()
+    }
+
+    pub fn args(&self) -> RArray {
+        let ruby = Ruby::get().unwrap();
+        let array = ruby.ary_new();
+        for arg in &self.args {
+            let _ = array.push(arg.clone());
+        }
+        array
+    }
+}
+
+fn render_arg(arg: &QueryPredicateArg, capture_names: &[String]) -> String {
+    match arg {
+        QueryPredicateArg::Capture(index) => format!("@{}", capture_names[*index as usize]),
+        QueryPredicateArg::String(s) => s.to_string(),
+    }
+}
+
+fn render_property(property: &tree_sitter::QueryProperty, capture_names: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(capture_id) = property.capture_id {
+        args.push(format!("@{}", capture_names[capture_id]));
+    }
+    args.push(property.key.to_string());
+    if let Some(value) = &property.value {
+        args.push(value.to_string());
+    }
+    args
+}
 
 #[magnus::wrap(class = "TreeSitter::Query")]
 pub struct Query {
     inner: tree_sitter::Query,
     capture_names: Vec<String>,
+    // All three indexed by pattern_index.
+    general_predicates: Vec<Vec<RawPredicate>>,
+    property_predicates: Vec<Vec<RawPredicate>>,
+    property_settings: Vec<Vec<RawPredicate>>,
 }
 
 impl Query {
@@ -21,15 +74,60 @@ impl Query {
             )
         })?;
 
-        let capture_names = query
+        let capture_names: Vec<String> = query
             .capture_names()
             .iter()
             .map(|s| s.to_string())
             .collect();
 
+        let mut general_predicates = Vec::with_capacity(query.pattern_count());
+        let mut property_predicates = Vec::with_capacity(query.pattern_count());
+        let mut property_settings = Vec::with_capacity(query.pattern_count());
+        for pattern_index in 0..query.pattern_count() {
+            general_predicates.push(
+                query
+                    .general_predicates(pattern_index)
+                    .iter()
+                    .map(|predicate| RawPredicate {
+                        operator: predicate.operator.to_string(),
+                        args: predicate
+                            .args
+                            .iter()
+                            .map(|arg| render_arg(arg, &capture_names))
+                            .collect(),
+                    })
+                    .collect(),
+            );
+
+            property_predicates.push(
+                query
+                    .property_predicates(pattern_index)
+                    .iter()
+                    .map(|(property, is_positive)| RawPredicate {
+                        operator: if *is_positive { "is?" } else { "is-not?" }.to_string(),
+                        args: render_property(property, &capture_names),
+                    })
+                    .collect(),
+            );
+
+            property_settings.push(
+                query
+                    .property_settings(pattern_index)
+                    .iter()
+                    .map(|property| RawPredicate {
+                        operator: "set!".to_string(),
+                        args: render_property(property, &capture_names),
+                    })
+                    .collect(),
+            );
+        }
+
         Ok(Self {
             inner: query,
             capture_names,
+            general_predicates,
+            property_predicates,
+            property_settings,
         })
     }
 
@@ -45,6 +143,47 @@ impl Query {
     pub fn pattern_count(&self) -> usize {
         self.inner.pattern_count()
     }
+
+    /// Predicates attached to `pattern_index` that aren't one of the standard
+    /// text predicates the crate already evaluates before a match is
+    /// yielded, and aren't `#set!`/`#is?`/`#is-not?` property predicates
+    /// either (see `property_predicates`/`property_settings` for those).
+    pub fn general_predicates(&self, pattern_index: usize) -> RArray {
+        let ruby = Ruby::get().unwrap();
+        let array = ruby.ary_new();
+        if let Some(predicates) = self.general_predicates.get(pattern_index) {
+            for predicate in predicates {
+                let _ = array.push(predicate.clone());
+            }
+        }
+        array
+    }
+
+    /// `#is?`/`#is-not?` predicates attached to `pattern_index`, verbatim —
+    /// the crate parses these but doesn't apply them, so it's up to the
+    /// caller to decide what the property means for a match.
+    pub fn property_predicates(&self, pattern_index: usize) -> RArray {
+        let ruby = Ruby::get().unwrap();
+        let array = ruby.ary_new();
+        if let Some(predicates) = self.property_predicates.get(pattern_index) {
+            for predicate in predicates {
+                let _ = array.push(predicate.clone());
+            }
+        }
+        array
+    }
+
+    /// `#set!` properties attached to `pattern_index`, verbatim.
+    pub fn property_settings(&self, pattern_index: usize) -> RArray {
+        let ruby = Ruby::get().unwrap();
+        let array = ruby.ary_new();
+        if let Some(predicates) = self.property_settings.get(pattern_index) {
+            for predicate in predicates {
+                let _ = array.push(predicate.clone());
+            }
+        }
+        array
+    }
 }
 
 #[magnus::wrap(class = "TreeSitter::QueryCursor")]
@@ -66,8 +205,9 @@ impl QueryCursor {
             return array;
         };
 
+        let source_bytes = source.as_bytes();
         let mut cursor = self.inner.borrow_mut();
-        let mut matches = cursor.matches(&query.inner, ts_node, source.as_bytes());
+        let mut matches = cursor.matches(&query.inner, ts_node, source_bytes);
 
         while let Some(m) = matches.next() {
             let captures: Vec<QueryCapture> = m
@@ -98,8 +238,9 @@ impl QueryCursor {
             return array;
         };
 
+        let source_bytes = source.as_bytes();
         let mut cursor = self.inner.borrow_mut();
-        let mut captures = cursor.captures(&query.inner, ts_node, source.as_bytes());
+        let mut captures = cursor.captures(&query.inner, ts_node, source_bytes);
 
         while let Some((m, capture_index)) = captures.next() {
             if let Some(c) = m.captures.get(*capture_index) {
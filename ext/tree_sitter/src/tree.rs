@@ -1,11 +1,17 @@
 use crate::language::{get_language_internal, Language};
 use crate::node::Node;
-use magnus::Error;
+use crate::point::Point;
+use magnus::{prelude::*, Error, RHash, Ruby, TryConvert, Value};
+use std::cell::RefCell;
+use std::os::fd::BorrowedFd;
 use std::sync::Arc;
 
 #[magnus::wrap(class = "TreeSitter::Tree")]
 pub struct Tree {
-    pub inner: Arc<tree_sitter::Tree>,
+    // Wrapped in a RefCell so `edit` can swap in an edited tree in place;
+    // the Arc is still cloned out to Nodes so existing Node snapshots keep
+    // pointing at the (unedited) tree they were built from.
+    pub inner: RefCell<Arc<tree_sitter::Tree>>,
     pub source: Arc<String>,
     pub language_name: String,
 }
@@ -13,15 +19,28 @@ pub struct Tree {
 impl Tree {
     pub fn new(tree: tree_sitter::Tree, source: String, language_name: String) -> Self {
         Self {
-            inner: Arc::new(tree),
+            inner: RefCell::new(Arc::new(tree)),
             source: Arc::new(source),
             language_name,
         }
     }
 
+    /// Returns the current underlying tree-sitter tree, for use as `old_tree`
+    /// in a subsequent `Parser#parse` call.
+    pub fn ts_tree(&self) -> Arc<tree_sitter::Tree> {
+        self.inner.borrow().clone()
+    }
+
     pub fn root_node(&self) -> Node {
-        let ts_node = self.inner.root_node();
-        Node::new(ts_node, self.source.clone(), self.inner.clone())
+        let tree = self.ts_tree();
+        let ts_node = tree.root_node();
+        Node::new(ts_node, self.source.clone(), tree)
+    }
+
+    pub fn walk(&self) -> crate::tree_cursor::TreeCursor {
+        let tree = self.ts_tree();
+        let ts_node = tree.root_node();
+        crate::tree_cursor::TreeCursor::new(tree, self.source.clone(), ts_node)
     }
 
     pub fn source(&self) -> String {
@@ -35,4 +54,78 @@ impl Tree {
             inner: ts_lang,
         })
     }
+
+    /// Records an edit so that a subsequent `Parser#parse(new_source, old_tree: tree)`
+    /// only re-scans the damaged region instead of reparsing from scratch.
+    /// Must be called with the new source already consistent with the given offsets/points.
+    pub fn edit(&self, args: &[Value]) -> Result<(), Error> {
+        let ruby = Ruby::get().unwrap();
+
+        let kwargs = args
+            .first()
+            .copied()
+            .and_then(RHash::from_value)
+            .ok_or_else(|| {
+                Error::new(
+                    ruby.exception_arg_error(),
+                    "expected keyword arguments: start_byte:, old_end_byte:, new_end_byte:, \
+                     start_point:, old_end_point:, new_end_point:",
+                )
+            })?;
+
+        let required = |name: &str| -> Result<Value, Error> {
+            kwargs.get(name).ok_or_else(|| {
+                Error::new(
+                    ruby.exception_arg_error(),
+                    format!("missing keyword: {}", name),
+                )
+            })
+        };
+
+        let start_byte: usize = TryConvert::try_convert(required("start_byte")?)?;
+        let old_end_byte: usize = TryConvert::try_convert(required("old_end_byte")?)?;
+        let new_end_byte: usize = TryConvert::try_convert(required("new_end_byte")?)?;
+        let start_point: &Point = TryConvert::try_convert(required("start_point")?)?;
+        let old_end_point: &Point = TryConvert::try_convert(required("old_end_point")?)?;
+        let new_end_point: &Point = TryConvert::try_convert(required("new_end_point")?)?;
+
+        if start_byte > old_end_byte {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "start_byte must not be greater than old_end_byte",
+            ));
+        }
+        if (start_point.row(), start_point.column()) > (old_end_point.row(), old_end_point.column())
+        {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                "start_point must not be greater than old_end_point",
+            ));
+        }
+
+        let edit = tree_sitter::InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+            start_position: start_point.to_ts(),
+            old_end_position: old_end_point.to_ts(),
+            new_end_position: new_end_point.to_ts(),
+        };
+
+        Arc::make_mut(&mut self.inner.borrow_mut()).edit(&edit);
+        Ok(())
+    }
+
+    /// Dumps the syntax tree as a GraphViz DOT document to `io` (any Ruby
+    /// `IO`/`File`), so it can be piped straight into `dot -Tsvg` without
+    /// shelling out to the `tree-sitter` CLI.
+    pub fn print_dot_graph(&self, io: Value) -> Result<(), Error> {
+        let fileno: i32 = io.funcall("fileno", ())?;
+        // SAFETY: `fileno` comes from a live Ruby IO object passed in by the
+        // caller, so the fd is valid for the duration of this call; we only
+        // borrow it, never close it.
+        let fd = unsafe { BorrowedFd::borrow_raw(fileno) };
+        self.ts_tree().print_dot_graph(&fd);
+        Ok(())
+    }
 }
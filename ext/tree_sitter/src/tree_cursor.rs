@@ -0,0 +1,75 @@
+use crate::node::Node;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// Wraps a `tree_sitter::TreeCursor`, giving O(1) navigation instead of the
+/// O(depth) `descendant_for_byte_range` relocation `Node`'s navigation
+/// methods rely on. Because the cursor tracks its position internally, it
+/// also resolves ambiguous byte ranges (zero-width/missing nodes, or a named
+/// node wrapping a single child) deterministically, since moving it never
+/// round-trips through byte offsets. The starting node handed to `Node#walk`
+/// or `reset` is still located in the tree by its byte range.
+#[magnus::wrap(class = "TreeSitter::TreeCursor")]
+pub struct TreeCursor {
+    // Declared (and thus dropped, per Rust's field drop order) before `tree`
+    // and `source`: `inner`'s `Drop` calls `ts_tree_cursor_delete`, which
+    // needs the tree's data still alive, so it must run before the `Arc<Tree>`
+    // below it is released. See the safety note on `new`.
+    inner: RefCell<tree_sitter::TreeCursor<'static>>,
+    // Kept alive for as long as `inner` holds a borrow into it; see the
+    // safety note on `new`.
+    #[allow(dead_code)]
+    tree: Arc<tree_sitter::Tree>,
+    source: Arc<String>,
+}
+
+impl TreeCursor {
+    pub fn new(
+        tree: Arc<tree_sitter::Tree>,
+        source: Arc<String>,
+        ts_node: tree_sitter::Node,
+    ) -> Self {
+        let cursor = ts_node.walk();
+        // SAFETY: `cursor` borrows from `ts_node`, which itself borrows from
+        // `tree`. We keep `tree` alive in this struct for exactly as long as
+        // `inner` does, and `tree` is an `Arc` whose heap data never moves,
+        // so extending the borrow to `'static` here is sound.
+        let cursor: tree_sitter::TreeCursor<'static> = unsafe { std::mem::transmute(cursor) };
+        Self {
+            inner: RefCell::new(cursor),
+            tree,
+            source,
+        }
+    }
+
+    pub fn goto_first_child(&self) -> bool {
+        self.inner.borrow_mut().goto_first_child()
+    }
+
+    pub fn goto_next_sibling(&self) -> bool {
+        self.inner.borrow_mut().goto_next_sibling()
+    }
+
+    pub fn goto_parent(&self) -> bool {
+        self.inner.borrow_mut().goto_parent()
+    }
+
+    pub fn goto_first_child_for_byte(&self, byte: usize) -> Option<usize> {
+        self.inner.borrow_mut().goto_first_child_for_byte(byte)
+    }
+
+    pub fn current_node(&self) -> Node {
+        let ts_node = self.inner.borrow().node();
+        Node::new(ts_node, self.source.clone(), self.tree.clone())
+    }
+
+    pub fn current_field_name(&self) -> Option<String> {
+        self.inner.borrow().field_name().map(|s| s.to_string())
+    }
+
+    pub fn reset(&self, node: &Node) {
+        if let Some(ts_node) = node.get_ts_node_pub() {
+            self.inner.borrow_mut().reset(ts_node);
+        }
+    }
+}